@@ -0,0 +1,32 @@
+//! Writer decorator that fans a slice of formatted records out to several child writers.
+
+use async_logger::Writer;
+
+/// Forwards every slice to each wrapped writer in turn, and flushes all of them on `flush`.
+///
+/// Used by `LoggerBuilder` to combine a primary writer (e.g. `FileWriter`) with any writers
+/// added via `add_writer`, so a single `Logger` can drive several destinations at once.
+pub(crate) struct CompositeWriter {
+    writers: Vec<Box<dyn Writer<Box<String>>>>,
+}
+
+impl CompositeWriter {
+    pub(crate) fn new(writers: Vec<Box<dyn Writer<Box<String>>>>) -> CompositeWriter {
+        CompositeWriter { writers }
+    }
+}
+
+impl Writer<Box<String>> for CompositeWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        for writer in &mut self.writers {
+            writer.process_slice(slice);
+        }
+    }
+
+    fn flush(&mut self) {
+        for writer in &mut self.writers {
+            writer.flush();
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Gzip compression of rotated log segments, behind the `compress` feature.
+//!
+//! `CompressingWriter` wraps another `Writer<Box<String>>` (typically `FileWriter`) and, each
+//! time rotation closes a segment and starts a new one, compresses the closed segment to
+//! `<name>.gz` on a background thread and removes the original. At most `keep_compressed`
+//! compressed files are kept in the log directory; older ones are deleted.
+
+use async_logger::Writer;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+const DEFAULT_KEEP_COMPRESSED: usize = 10;
+
+/// Wraps a `Writer<Box<String>>` and gzip-compresses each segment file as soon as rotation
+/// closes it, keeping at most `keep_compressed` compressed files around.
+pub struct CompressingWriter<W: Writer<Box<String>>> {
+    inner: W,
+    log_dir: PathBuf,
+    keep_compressed: usize,
+    active_file: Option<PathBuf>,
+}
+
+impl<W: Writer<Box<String>>> CompressingWriter<W> {
+
+    /// Wrap `inner`, watching `log_dir` (the directory `inner` rotates files in) for newly
+    /// closed segments to compress.
+    pub fn new(inner: W, log_dir: &str) -> CompressingWriter<W> {
+
+        let log_dir = PathBuf::from(log_dir);
+        let active_file = newest_log_file(&log_dir);
+
+        CompressingWriter {
+            inner,
+            log_dir,
+            keep_compressed: DEFAULT_KEEP_COMPRESSED,
+            active_file,
+        }
+    }
+
+    /// Keep at most `n` compressed files, deleting the oldest beyond that. Default is 10.
+    pub fn keep_compressed(mut self, n: usize) -> Self {
+        self.keep_compressed = n;
+        self
+    }
+
+    fn check_rotation(&mut self) {
+
+        let newest = newest_log_file(&self.log_dir);
+
+        if newest != self.active_file {
+            if let Some(closed) = self.active_file.take() {
+                spawn_compress(closed, self.log_dir.clone(), self.keep_compressed);
+            }
+
+            self.active_file = newest;
+        }
+    }
+}
+
+impl<W: Writer<Box<String>>> Writer<Box<String>> for CompressingWriter<W> {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        self.inner.process_slice(slice);
+        self.check_rotation();
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush();
+        self.check_rotation();
+    }
+}
+
+fn newest_log_file(dir: &Path) -> Option<PathBuf> {
+
+    fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().map_or(true, |ext| ext != "gz"))
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+fn spawn_compress(file: PathBuf, log_dir: PathBuf, keep_compressed: usize) {
+
+    thread::spawn(move || {
+
+        if let Err(e) = compress_file(&file) {
+            eprintln!("async_logger_log: failed to compress {}: {}", file.display(), e);
+            return;
+        }
+
+        if let Err(e) = enforce_retention(&log_dir, keep_compressed) {
+            eprintln!("async_logger_log: failed to enforce compressed log retention in {}: {}", log_dir.display(), e);
+        }
+    });
+}
+
+fn compress_file(path: &Path) -> io::Result<()> {
+
+    let mut input = File::open(path)?;
+
+    let mut gz_name = path.file_name().expect("log segment path has no file name").to_os_string();
+    gz_name.push(".gz");
+    let gz_path = path.with_file_name(gz_name);
+
+    let output = File::create(&gz_path)?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
+
+fn enforce_retention(dir: &Path, keep: usize) -> io::Result<()> {
+
+    let mut gz_files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "gz"))
+        .collect();
+
+    gz_files.sort_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+
+    while gz_files.len() > keep {
+        fs::remove_file(gz_files.remove(0))?;
+    }
+
+    Ok(())
+}
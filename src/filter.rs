@@ -0,0 +1,161 @@
+//! Per-target log level filtering, parsed from an env-style filter spec.
+//!
+//! A spec looks like `info,my_crate=debug,my_crate::net=trace,noisy=off`: an
+//! optional default level followed by comma-separated `target=level`
+//! directives. When matching a record's target, the directive whose target
+//! is the longest prefix of the record's target wins; targets that match no
+//! directive fall back to the default level.
+
+use log::{Level, LevelFilter};
+use std::fmt;
+
+/// A single `target=level` directive.
+#[derive(Debug, Clone)]
+struct Directive {
+    target: String,
+    level: LevelFilter,
+}
+
+/// A parsed filter spec: a default level plus per-target overrides.
+///
+/// Directives are kept sorted by descending target length, so the first
+/// directive whose target is a prefix of a record's target is always the
+/// most specific match.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    default_level: LevelFilter,
+    directives: Vec<Directive>,
+}
+
+/// Error returned when a filter spec fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterParseError(String);
+
+impl fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid filter spec: {}", self.0)
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
+impl Filter {
+
+    /// Parse a comma-separated filter spec such as
+    /// `info,my_crate=debug,my_crate::net=trace,noisy=off`.
+    ///
+    /// A bare level sets the default level. A `target=level` pair restricts
+    /// that level to targets prefixed by `target`. The whole spec is
+    /// rejected if any directive is malformed.
+    pub fn parse(spec: &str) -> Result<Filter, FilterParseError> {
+
+        let mut default_level = LevelFilter::max();
+        let mut directives = Vec::new();
+
+        for part in spec.split(',') {
+
+            let part = part.trim();
+
+            if part.is_empty() {
+                continue;
+            }
+
+            match part.find('=') {
+                Some(pos) => {
+                    let target = part[..pos].trim();
+                    let level = part[pos + 1..].trim();
+
+                    if target.is_empty() {
+                        return Err(FilterParseError(format!("empty target in directive '{}'", part)));
+                    }
+
+                    directives.push(Directive {
+                        target: target.to_string(),
+                        level: parse_level(level)?,
+                    });
+                },
+                None => {
+                    default_level = parse_level(part)?;
+                },
+            }
+        }
+
+        directives.sort_by(|a, b| b.target.len().cmp(&a.target.len()));
+
+        Ok(Filter { default_level, directives })
+    }
+
+    /// Returns true if a record at `target` with `level` passes this filter.
+    pub fn is_enabled(&self, target: &str, level: Level) -> bool {
+        level <= self.level_for(target)
+    }
+
+    /// The loosest level this filter can ever let through, across the default level and every
+    /// directive. Callers must raise `log::max_level()` to at least this, or the `log` crate's
+    /// macros will statically discard records before `Logger::enabled` ever gets a chance to
+    /// apply the finer-grained per-target filtering.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives.iter()
+            .map(|d| d.level)
+            .fold(self.default_level, std::cmp::max)
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+
+        for directive in &self.directives {
+            if target.starts_with(directive.target.as_str()) {
+                return directive.level;
+            }
+        }
+
+        self.default_level
+    }
+}
+
+fn parse_level(s: &str) -> Result<LevelFilter, FilterParseError> {
+    s.parse::<LevelFilter>().map_err(|_| FilterParseError(format!("unknown level '{}'", s)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_default_only() {
+        let filter = Filter::parse("info").unwrap();
+        assert!(filter.is_enabled("any::target", Level::Info));
+        assert!(!filter.is_enabled("any::target", Level::Debug));
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let filter = Filter::parse("info,my_crate=debug,my_crate::net=trace,noisy=off").unwrap();
+
+        assert!(filter.is_enabled("unrelated", Level::Info));
+        assert!(!filter.is_enabled("unrelated", Level::Debug));
+
+        assert!(filter.is_enabled("my_crate", Level::Debug));
+        assert!(!filter.is_enabled("my_crate", Level::Trace));
+
+        assert!(filter.is_enabled("my_crate::net", Level::Trace));
+        assert!(filter.is_enabled("my_crate::net::sub", Level::Trace));
+
+        assert!(!filter.is_enabled("noisy", Level::Error));
+    }
+
+    #[test]
+    fn test_invalid_spec() {
+        assert!(Filter::parse("my_crate=not_a_level").is_err());
+        assert!(Filter::parse("=debug").is_err());
+    }
+
+    #[test]
+    fn test_max_level_is_loosest_of_default_and_directives() {
+        let filter = Filter::parse("info,my_crate=debug,my_crate::net=trace,noisy=off").unwrap();
+        assert_eq!(LevelFilter::Trace, filter.max_level());
+
+        let filter = Filter::parse("warn").unwrap();
+        assert_eq!(LevelFilter::Warn, filter.max_level());
+    }
+}
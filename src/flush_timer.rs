@@ -0,0 +1,63 @@
+//! Background thread that flushes the async queue on a fixed cadence, bounding the latency
+//! before a record reaches disk even when traffic is too low to fill a buffer.
+
+use async_logger::AsyncLoggerNB;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Owns the background thread spawned by `LoggerBuilder::flush_interval`. Stops and joins the
+/// thread on drop.
+pub(crate) struct FlushTimer {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FlushTimer {
+
+    pub(crate) fn spawn(interval: Duration, async_logger: Arc<AsyncLoggerNB<Box<String>>>) -> FlushTimer {
+
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let stop_thread = Arc::clone(&stop);
+
+        let handle = std::thread::spawn(move || {
+
+            let (lock, cvar) = &*stop_thread;
+            let mut stopped = lock.lock().expect("flush timer lock poisoned");
+
+            loop {
+
+                let (guard, timeout_result) = cvar.wait_timeout(stopped, interval)
+                    .expect("flush timer lock poisoned");
+
+                stopped = guard;
+
+                if *stopped {
+                    break;
+                }
+
+                if timeout_result.timed_out() {
+                    AsyncLoggerNB::flush(&async_logger);
+                }
+            }
+        });
+
+        FlushTimer { stop, handle: Some(handle) }
+    }
+}
+
+impl Drop for FlushTimer {
+
+    fn drop(&mut self) {
+
+        {
+            let (lock, cvar) = &*self.stop;
+            *lock.lock().expect("flush timer lock poisoned") = true;
+            cvar.notify_one();
+        }
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Opt-in Bunyan-style structured JSON formatter, enabled with the `json` feature and
+//! `LoggerBuilder::json_format`.
+//!
+//! Each record is emitted as a single JSON object per line (schema version 0), suitable for
+//! ingestion by log shipping/aggregation tooling that the free-form text format isn't.
+
+use log::{Level, Record};
+use std::process;
+
+/// Builds the Bunyan-style JSON line for each record. Created once per `Logger` by
+/// `LoggerBuilder::json_format` and captured in the formatter closure, so the `name` field is
+/// per-`Logger` state rather than something every `Logger` in the process has to share.
+pub(crate) struct JsonFormatter {
+    name: String,
+    hostname: String,
+    pid: u32,
+}
+
+impl JsonFormatter {
+
+    pub(crate) fn new(name: &str) -> JsonFormatter {
+        JsonFormatter {
+            name: name.to_string(),
+            hostname: hostname(),
+            pid: process::id(),
+        }
+    }
+
+    /// Format a record as a single-line Bunyan-style JSON object.
+    pub(crate) fn format(&self, record: &Record) -> String {
+
+        let mut out = String::with_capacity(160);
+
+        out.push('{');
+
+        out.push_str("\"v\":0,");
+
+        out.push_str("\"name\":");
+        push_json_string(&mut out, &self.name);
+        out.push(',');
+
+        out.push_str("\"hostname\":");
+        push_json_string(&mut out, &self.hostname);
+        out.push(',');
+
+        out.push_str("\"pid\":");
+        out.push_str(&self.pid.to_string());
+        out.push(',');
+
+        out.push_str("\"time\":");
+        push_json_string(&mut out, &now_rfc3339());
+        out.push(',');
+
+        out.push_str("\"level\":");
+        out.push_str(&bunyan_level(record.level()).to_string());
+        out.push(',');
+
+        out.push_str("\"target\":");
+        push_json_string(&mut out, record.target());
+        out.push(',');
+
+        if let Some(module) = record.module_path() {
+            out.push_str("\"module\":");
+            push_json_string(&mut out, module);
+            out.push(',');
+        }
+
+        #[cfg(feature = "kv")]
+        push_key_values(&mut out, record);
+
+        out.push_str("\"msg\":");
+        push_json_string(&mut out, &record.args().to_string());
+
+        out.push('}');
+        out.push('\n');
+
+        out
+    }
+}
+
+fn bunyan_level(level: Level) -> u8 {
+    match level {
+        Level::Trace => 10,
+        Level::Debug => 20,
+        Level::Info => 30,
+        Level::Warn => 40,
+        Level::Error => 50,
+    }
+}
+
+#[cfg(feature = "kv")]
+fn push_key_values(out: &mut String, record: &Record) {
+
+    struct Visitor<'a> {
+        out: &'a mut String,
+    }
+
+    impl<'kvs, 'a> log::kv::VisitSource<'kvs> for Visitor<'a> {
+        fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+            self.out.push_str("\"");
+            self.out.push_str(key.as_str());
+            self.out.push_str("\":");
+            push_json_string(self.out, &value.to_string());
+            self.out.push(',');
+            Ok(())
+        }
+    }
+
+    let _ = record.key_values().visit(&mut Visitor { out });
+}
+
+fn push_json_string(out: &mut String, s: &str) {
+
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+fn hostname() -> String {
+
+    if let Ok(name) = std::fs::read_to_string("/proc/sys/kernel/hostname") {
+        return name.trim().to_string();
+    }
+
+    std::env::var("HOSTNAME").unwrap_or_else(|_| String::from("unknown"))
+}
+
+#[cfg(feature = "time")]
+fn now_rfc3339() -> String {
+    time::OffsetDateTime::now_local().format("%Y-%m-%dT%H:%M:%S.%N%z")
+}
+
+#[cfg(not(feature = "time"))]
+fn now_rfc3339() -> String {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or(std::time::Duration::new(0, 0))
+        .as_secs()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_bunyan_level() {
+        assert_eq!(10, bunyan_level(Level::Trace));
+        assert_eq!(20, bunyan_level(Level::Debug));
+        assert_eq!(30, bunyan_level(Level::Info));
+        assert_eq!(40, bunyan_level(Level::Warn));
+        assert_eq!(50, bunyan_level(Level::Error));
+    }
+
+    #[test]
+    fn test_push_json_string_escapes() {
+        let mut out = String::new();
+        push_json_string(&mut out, "a\"b\\c\nd");
+        assert_eq!("\"a\\\"b\\\\c\\nd\"", out);
+    }
+}
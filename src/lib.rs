@@ -84,6 +84,16 @@
 //! [dependencies.async_logger_log]
 //! default-features = false
 //! ```
+//!
+//! Enabling the `json` feature adds `LoggerBuilder::json_format`, a Bunyan-style structured
+//! formatter that emits one JSON object per line instead of the default text line.
+//!
+//! Enabling the `syslog` feature adds the `syslog` module, with a `SyslogWriter` that can be
+//! plugged in through `LoggerBuilder::syslog` to send records to the local syslog/journald
+//! socket, or to a remote syslog host over UDP/TCP, alongside the primary writer.
+//!
+//! Enabling the `compress` feature adds the `compress` module, with a `CompressingWriter` that
+//! wraps another writer (typically `FileWriter`) and gzip-compresses each rotated segment.
 
 
 
@@ -93,14 +103,34 @@ extern crate log;
 #[cfg(feature="time")]
 extern crate time;
 
+#[cfg(feature="compress")]
+extern crate flate2;
+
+
+mod composite;
+mod filter;
+mod flush_timer;
+mod spec_file;
+
+#[cfg(feature = "compress")]
+pub mod compress;
 
-use log::{Log, Metadata, Record};
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "syslog")]
+pub mod syslog;
+
+use log::{Level, Log, Metadata, Record};
 use async_logger::{AsyncLoggerNB, FileWriter, Error};
-use std::sync::Arc;
+use std::io::IsTerminal;
+use std::sync::{Arc, RwLock};
 
 #[cfg(feature="time")]
 use time::OffsetDateTime;
 
+pub use filter::{Filter, FilterParseError};
+
 
 const DEFAULT_BUF_SIZE: usize = 256;
 const DEFAULT_LOG_FILE_SIZE: usize = 10*1024*1024;
@@ -109,7 +139,13 @@ const DEFAULT_LOG_FILE_SIZE: usize = 10*1024*1024;
 /// Log trait implementation.
 pub struct Logger {
     async_logger: Arc<AsyncLoggerNB<Box<String>>>,
-    formatter: fn(&Record) -> String,
+    formatter: Box<dyn Fn(&Record) -> String + Send + Sync>,
+    filter: Arc<RwLock<Option<Filter>>>,
+    duplicate_to_stderr: Option<Level>,
+    #[cfg(feature = "syslog")]
+    syslog: Option<Arc<AsyncLoggerNB<Box<(Level, String)>>>>,
+    _spec_watcher: Option<spec_file::SpecFileWatcher>,
+    _flush_timer: Option<flush_timer::FlushTimer>,
 }
 
 impl Logger {
@@ -125,11 +161,17 @@ impl Logger {
 
         let async_logger = Arc::new(AsyncLoggerNB::new(Box::new(writer), buf_sz)?);
 
-        let formatter = Logger::format_msg;
+        let formatter: Box<dyn Fn(&Record) -> String + Send + Sync> = Box::new(Logger::format_msg);
 
         Ok(Logger {
             async_logger,
             formatter,
+            filter: Arc::new(RwLock::new(None)),
+            duplicate_to_stderr: None,
+            #[cfg(feature = "syslog")]
+            syslog: None,
+            _spec_watcher: None,
+            _flush_timer: None,
         })
     }
 
@@ -138,26 +180,79 @@ impl Logger {
         LoggerBuilder {
             buf_sz: None,
             writer: None,
+            writers: Vec::new(),
             formatter: None,
+            colored: false,
+            filter: None,
+            spec_file: None,
+            duplicate_to_stderr: None,
+            #[cfg(feature = "syslog")]
+            syslog: None,
+            flush_interval: None,
         }
     }
 
+    /// Replace the active filter spec at runtime, e.g. to raise or lower verbosity on a running
+    /// process without restarting it. See `LoggerBuilder::filter` for the spec syntax.
+    pub fn set_filter(&self, spec: &str) -> Result<(), FilterParseError> {
+
+        let filter = Filter::parse(spec)?;
+
+        // The `log` crate's macros discard records below `log::max_level()` before `Logger::log`
+        // is ever called, so the global max level has to track the loosest level this filter can
+        // let through or per-target directives that loosen verbosity would never fire.
+        log::set_max_level(filter.max_level());
+
+        *self.filter.write().expect("filter lock poisoned") = Some(filter);
+
+        Ok(())
+    }
+
     fn format_msg(record: &Record) -> String {
 
-        let time;
-        #[cfg(feature="time")] 
+        format!("[{} {} {}]: {}\n", Logger::current_time(), record.level(), record.target(), record.args())
+    }
+
+    fn current_time() -> String {
+
+        #[cfg(feature="time")]
         {
-            time = OffsetDateTime::now_local().format("%Y-%m-%d %H:%M:%S.%N%z");
+            OffsetDateTime::now_local().format("%Y-%m-%d %H:%M:%S.%N%z")
         }
-        #[cfg(not(feature="time"))] 
+        #[cfg(not(feature="time"))]
         {
-            time = std::time::SystemTime::now()
+            std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or(std::time::Duration::new(0,0))
-                .as_secs();
+                .as_secs()
+                .to_string()
         }
+    }
+
+    /// A preset formatter matching the default text format, except the level is wrapped in ANSI
+    /// color codes when `use_color` is set (see `LoggerBuilder::colored_format` for when that is).
+    /// Only possible because formatters can now capture state (here, whether color should be used).
+    fn colored_msg(use_color: bool, record: &Record) -> String {
 
-        format!("[{} {} {}]: {}\n", time, record.level(), record.target(), record.args())
+        let level = record.level();
+
+        let level_str = if use_color {
+            format!("\x1b[{}m{}\x1b[0m", ansi_color_code(level), level)
+        } else {
+            level.to_string()
+        };
+
+        format!("[{} {} {}]: {}\n", Logger::current_time(), level_str, record.target(), record.args())
+    }
+}
+
+fn ansi_color_code(level: log::Level) -> u8 {
+    match level {
+        log::Level::Error => 31,
+        log::Level::Warn => 33,
+        log::Level::Info => 32,
+        log::Level::Debug => 36,
+        log::Level::Trace => 37,
     }
 }
 
@@ -165,19 +260,42 @@ impl Log for Logger {
 
     fn enabled(&self, metadata: &Metadata) -> bool {
 
-        return metadata.level() <= log::max_level();
+        match self.filter.read().expect("filter lock poisoned").as_ref() {
+            Some(filter) => filter.is_enabled(metadata.target(), metadata.level()),
+            None => metadata.level() <= log::max_level(),
+        }
     }
 
     fn log(&self, record: &Record) {
 
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
         let msg = (self.formatter)(record);
 
+        if let Some(threshold) = self.duplicate_to_stderr {
+            if record.level() <= threshold {
+                eprint!("{}", msg);
+            }
+        }
+
+        #[cfg(feature = "syslog")]
+        if let Some(syslog) = &self.syslog {
+            let _ = syslog.write_value(Box::new((record.level(), record.args().to_string())));
+        }
+
         let _ = self.async_logger.write_value(Box::new(msg));
     }
 
     fn flush(&self) {
 
         AsyncLoggerNB::flush(&self.async_logger);
+
+        #[cfg(feature = "syslog")]
+        if let Some(syslog) = &self.syslog {
+            AsyncLoggerNB::flush(syslog);
+        }
     }
 }
 
@@ -186,7 +304,15 @@ impl Log for Logger {
 pub struct LoggerBuilder {
     buf_sz: Option<usize>,
     writer: Option<Box<dyn async_logger::Writer<Box<String>>>>,
-    formatter: Option<fn(&Record) -> String>,
+    writers: Vec<Box<dyn async_logger::Writer<Box<String>>>>,
+    formatter: Option<Box<dyn Fn(&Record) -> String + Send + Sync>>,
+    colored: bool,
+    filter: Option<Filter>,
+    spec_file: Option<std::path::PathBuf>,
+    duplicate_to_stderr: Option<Level>,
+    #[cfg(feature = "syslog")]
+    syslog: Option<syslog::SyslogWriter>,
+    flush_interval: Option<std::time::Duration>,
 }
 
 impl LoggerBuilder {
@@ -199,7 +325,30 @@ impl LoggerBuilder {
 
     /// Set custom formatter of log records.
     pub fn formatter(mut self, formatter: fn(&Record) -> String) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self.colored = false;
+        self
+    }
+
+    /// Set a custom formatter that may capture state, e.g. a hostname, a colorizer, or a
+    /// counter, which a plain `fn` pointer passed to `formatter` cannot.
+    pub fn formatter_boxed(mut self, formatter: Box<dyn Fn(&Record) -> String + Send + Sync>) -> Self {
         self.formatter = Some(formatter);
+        self.colored = false;
+        self
+    }
+
+    /// Use the default text format, except the level is wrapped in ANSI color codes.
+    ///
+    /// The primary writer (a `FileWriter` by default) writes to a file, not a terminal, so
+    /// whether color is actually wanted can't be decided by checking the process's own stdout:
+    /// that's true or false regardless of where records end up. Instead, color is used only when
+    /// `duplicate_to_stderr` is also configured and stderr (the stream a human could actually be
+    /// watching) is a terminal; call order between the two builder methods doesn't matter, since
+    /// this is resolved once in `build`.
+    pub fn colored_format(mut self) -> Self {
+        self.formatter = None;
+        self.colored = true;
         self
     }
 
@@ -209,6 +358,92 @@ impl LoggerBuilder {
         self
     }
 
+    /// Add an extra writer that every record is also sent to, in addition to the primary
+    /// writer set through `writer`. May be called repeatedly to drive several destinations at
+    /// once; all writers are flushed together. If `writer` is not called, the default
+    /// `FileWriter` is not created and records go only to the writers added here.
+    pub fn add_writer(mut self, writer: Box<dyn async_logger::Writer<Box<String>>>) -> Self {
+        self.writers.push(writer);
+        self
+    }
+
+    /// Also echo, synchronously and immediately, any record at or above `level` to stderr. This
+    /// bypasses the async queue, so warnings/errors surface on the console right away while the
+    /// full log still goes through the configured writer(s).
+    pub fn duplicate_to_stderr(mut self, level: Level) -> Self {
+        self.duplicate_to_stderr = Some(level);
+        self
+    }
+
+    /// Also send every record to `writer` over syslog (requires the `syslog` feature), on its
+    /// own non-blocking queue independent of the primary writer's, so the severity can be taken
+    /// from the real `Record` rather than the shared formatted `String` without making syslog's
+    /// socket I/O part of the primary writer's queue.
+    #[cfg(feature = "syslog")]
+    pub fn syslog(mut self, writer: syslog::SyslogWriter) -> Self {
+        self.syslog = Some(writer);
+        self
+    }
+
+    /// Switch to the Bunyan-style structured JSON formatter (requires the `json` feature).
+    /// `name` is the service name reported in each record's `name` field. Emits one JSON object
+    /// per line instead of the default human-readable text line.
+    #[cfg(feature = "json")]
+    pub fn json_format(mut self, name: &str) -> Self {
+        let formatter = json::JsonFormatter::new(name);
+        self.formatter = Some(Box::new(move |record: &Record| formatter.format(record)));
+        self.colored = false;
+        self
+    }
+
+    /// Set a per-target filter spec, e.g. `"info,my_crate=debug,my_crate::net=trace,noisy=off"`.
+    ///
+    /// The spec is a comma-separated list of directives: a bare level sets the default, and a
+    /// `target=level` pair overrides the level for any target prefixed by `target`. The most
+    /// specific (longest) matching target wins. Records below the matched level are dropped
+    /// before they are formatted.
+    ///
+    /// If the spec fails to parse, a warning is printed to stderr and the previous filter (or
+    /// the `log::max_level()` default if none was set) is kept.
+    ///
+    /// `log::max_level()` is set to match once the `Logger` is actually built by `build`, not
+    /// here, so a builder that's discarded (or whose `build` fails) never mutates that global.
+    pub fn filter(mut self, spec: &str) -> Self {
+
+        match Filter::parse(spec) {
+            Ok(filter) => self.filter = Some(filter),
+            Err(e) => eprintln!("async_logger_log: {}, ignoring filter spec '{}'", e, spec),
+        }
+
+        self
+    }
+
+    /// Load a per-target filter spec from `path` (see `filter` for the syntax) and keep it
+    /// live: a background thread watches the file for modifications and swaps the active
+    /// filter in whenever it changes. If the file can't be read or parsed, either initially or
+    /// after a later change, a warning is printed to stderr and the previous filter is kept.
+    pub fn spec_file<P: Into<std::path::PathBuf>>(mut self, path: P) -> Self {
+
+        let path = path.into();
+
+        match std::fs::read_to_string(&path) {
+            Ok(spec) => self = self.filter(spec.trim()),
+            Err(e) => eprintln!("async_logger_log: failed to read spec file {}: {}", path.display(), e),
+        }
+
+        self.spec_file = Some(path);
+
+        self
+    }
+
+    /// Periodically flush the async queue on a background thread at the given cadence, so a
+    /// low-traffic process doesn't leave recent records sitting in memory indefinitely. The
+    /// thread is stopped and joined when the built `Logger` is dropped.
+    pub fn flush_interval(mut self, interval: std::time::Duration) -> Self {
+        self.flush_interval = Some(interval);
+        self
+    }
+
     /// Build the `Logger` instance.
     pub fn build(self) -> Result<Logger,Error> {
 
@@ -217,21 +452,61 @@ impl LoggerBuilder {
             None => DEFAULT_BUF_SIZE,
         };
         
-        let writer = match self.writer {
-            Some(writer) => writer,
-            None => Box::new(FileWriter::new(".", DEFAULT_LOG_FILE_SIZE)?),
+        let mut writers = self.writers;
+
+        match self.writer {
+            Some(writer) => writers.insert(0, writer),
+            None if writers.is_empty() => writers.push(Box::new(FileWriter::new(".", DEFAULT_LOG_FILE_SIZE)?)),
+            None => {},
+        }
+
+        let writer: Box<dyn async_logger::Writer<Box<String>>> = if writers.len() == 1 {
+            writers.pop().expect("writers checked non-empty")
+        } else {
+            Box::new(composite::CompositeWriter::new(writers))
         };
 
-        let formatter = match self.formatter {
+        let formatter: Box<dyn Fn(&Record) -> String + Send + Sync> = match self.formatter {
             Some(formatter) => formatter,
-            None => Logger::format_msg,
+            None if self.colored => {
+                let use_color = self.duplicate_to_stderr.is_some() && std::io::stderr().is_terminal();
+                Box::new(move |record: &Record| Logger::colored_msg(use_color, record))
+            },
+            None => Box::new(Logger::format_msg),
         };
 
         let async_logger = Arc::new(AsyncLoggerNB::new(writer, buf_sz)?);
 
+        #[cfg(feature = "syslog")]
+        let syslog = match self.syslog {
+            Some(writer) => Some(Arc::new(AsyncLoggerNB::new(Box::new(writer), buf_sz)?)),
+            None => None,
+        };
+
+        // See the comment in `Logger::set_filter`: the global max level has to track the loosest
+        // level this filter can let through, or `log`'s macros would statically discard records
+        // before per-target directives get a chance to apply. Done here, once the `Logger` is
+        // actually built, rather than in `filter`/`spec_file`, so a discarded builder (or one
+        // whose `build` fails) never mutates that global.
+        if let Some(filter) = &self.filter {
+            log::set_max_level(filter.max_level());
+        }
+
+        let filter = Arc::new(RwLock::new(self.filter));
+
+        let spec_watcher = self.spec_file.map(|path| spec_file::SpecFileWatcher::spawn(path, Arc::clone(&filter)));
+
+        let flush_timer = self.flush_interval.map(|interval| flush_timer::FlushTimer::spawn(interval, Arc::clone(&async_logger)));
+
         Ok(Logger {
             async_logger,
             formatter,
+            filter,
+            duplicate_to_stderr: self.duplicate_to_stderr,
+            #[cfg(feature = "syslog")]
+            syslog,
+            _spec_watcher: spec_watcher,
+            _flush_timer: flush_timer,
         })
     }
 }
@@ -0,0 +1,97 @@
+//! Background thread that watches a filter spec file for modifications and reloads it, so
+//! verbosity can be raised or lowered on a running process without a restart.
+
+use crate::filter::Filter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Owns the background thread spawned by `LoggerBuilder::spec_file`. Stops and joins the
+/// thread on drop; the wait is `Condvar`-backed (like `flush_timer::FlushTimer`) so drop wakes
+/// the thread immediately instead of waiting out the remainder of `POLL_INTERVAL`.
+pub(crate) struct SpecFileWatcher {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SpecFileWatcher {
+
+    pub(crate) fn spawn(path: PathBuf, filter: Arc<RwLock<Option<Filter>>>) -> SpecFileWatcher {
+
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let stop_thread = Arc::clone(&stop);
+
+        let handle = std::thread::spawn(move || {
+
+            let mut last_modified: Option<SystemTime> = None;
+
+            let (lock, cvar) = &*stop_thread;
+            let mut stopped = lock.lock().expect("spec file watcher lock poisoned");
+
+            loop {
+
+                if let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) {
+                    if Some(modified) != last_modified {
+                        last_modified = Some(modified);
+                        reload(&path, &filter);
+                    }
+                }
+
+                let (guard, timeout_result) = cvar.wait_timeout(stopped, POLL_INTERVAL)
+                    .expect("spec file watcher lock poisoned");
+
+                stopped = guard;
+
+                if *stopped {
+                    break;
+                }
+
+                debug_assert!(timeout_result.timed_out());
+            }
+        });
+
+        SpecFileWatcher { stop, handle: Some(handle) }
+    }
+}
+
+fn reload(path: &Path, filter: &Arc<RwLock<Option<Filter>>>) {
+
+    match std::fs::read_to_string(path) {
+        Ok(spec) => match Filter::parse(spec.trim()) {
+            Ok(new_filter) => {
+                // See `Logger::set_filter`: the global max level has to track the loosest level
+                // the new filter can let through, or `log`'s macros would statically discard
+                // records before the hot-reloaded directives get a chance to apply.
+                log::set_max_level(new_filter.max_level());
+                *filter.write().expect("filter lock poisoned") = Some(new_filter);
+            },
+            Err(e) => eprintln!(
+                "async_logger_log: {} in spec file {}, keeping previous filter",
+                e, path.display(),
+            ),
+        },
+        Err(e) => eprintln!(
+            "async_logger_log: failed to read spec file {}: {}, keeping previous filter",
+            path.display(), e,
+        ),
+    }
+}
+
+impl Drop for SpecFileWatcher {
+
+    fn drop(&mut self) {
+
+        {
+            let (lock, cvar) = &*self.stop;
+            *lock.lock().expect("spec file watcher lock poisoned") = true;
+            cvar.notify_one();
+        }
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
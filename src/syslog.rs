@@ -0,0 +1,185 @@
+//! `SyslogWriter` sends records to the local syslog socket (`/dev/log` on Linux) or to a remote
+//! syslog host over UDP/TCP, framed as RFC 5424. Enabled with the `syslog` feature. The transport
+//! is chosen by the constructor; `new` returns an error when `/dev/log` can't be reached and does
+//! not fall back to a remote host.
+//!
+//! Severity is part of RFC 5424 framing, not something the free-form formatted message carries,
+//! so `SyslogWriter` doesn't share the primary writer's `Box<String>` queue: `Logger` drives it
+//! through its own `AsyncLoggerNB<Box<(Level, String)>>`, carrying the level alongside the
+//! message so severity stays correct regardless of what formatter the `Logger` is configured
+//! with. Framing and the (possibly blocking, reconnect-and-retry) socket write both happen on
+//! that queue's own writer thread, not on the caller's thread.
+
+use log::Level;
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::process;
+
+fn severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+enum Socket {
+    Unix(UnixDatagram),
+    Udp(UdpSocket, SocketAddr),
+    Tcp(TcpStream, SocketAddr),
+}
+
+/// Sends log records to syslog/journald over `/dev/log`, or to a remote syslog host over
+/// UDP/TCP, framed as RFC 5424 (`<PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID - MSG`).
+/// Plug into a `Logger` via `LoggerBuilder::syslog`.
+pub struct SyslogWriter {
+    facility: u8,
+    app_name: String,
+    hostname: String,
+    pid: u32,
+    socket: Socket,
+}
+
+impl SyslogWriter {
+
+    /// Connect to the local syslog socket (`/dev/log` on Linux).
+    pub fn new(app_name: &str) -> io::Result<SyslogWriter> {
+        Ok(SyslogWriter::with_socket(app_name, Socket::Unix(connect_unix()?)))
+    }
+
+    /// Connect to a remote syslog host over UDP.
+    pub fn new_udp<A: ToSocketAddrs>(app_name: &str, addr: A) -> io::Result<SyslogWriter> {
+        let addr = resolve(addr)?;
+        Ok(SyslogWriter::with_socket(app_name, Socket::Udp(bind_udp(addr)?, addr)))
+    }
+
+    /// Connect to a remote syslog host over TCP.
+    pub fn new_tcp<A: ToSocketAddrs>(app_name: &str, addr: A) -> io::Result<SyslogWriter> {
+        let addr = resolve(addr)?;
+        Ok(SyslogWriter::with_socket(app_name, Socket::Tcp(TcpStream::connect(addr)?, addr)))
+    }
+
+    fn with_socket(app_name: &str, socket: Socket) -> SyslogWriter {
+        SyslogWriter {
+            facility: 1, // user-level messages
+            app_name: app_name.to_string(),
+            hostname: hostname(),
+            pid: process::id(),
+            socket,
+        }
+    }
+
+    /// Set the syslog facility code (`0`-`23`, per RFC 5424's facility table). Default is `1`
+    /// (user-level messages). Out-of-range values are ignored with a warning printed to stderr,
+    /// keeping the previous facility, so `frame`'s `pri` computation can never overflow.
+    pub fn facility(mut self, facility: u8) -> Self {
+        if facility <= 23 {
+            self.facility = facility;
+        } else {
+            eprintln!("async_logger_log: syslog facility {} is outside the RFC 5424 range (0-23), ignoring", facility);
+        }
+        self
+    }
+
+    fn frame(&self, level: Level, msg: &str) -> String {
+
+        let pri = self.facility * 8 + severity(level);
+
+        format!(
+            "<{}>1 {} {} {} {} - - {}\n",
+            pri, timestamp(), self.hostname, self.app_name, self.pid, msg,
+        )
+    }
+
+    fn send(&mut self, frame: &str) -> io::Result<()> {
+        match &mut self.socket {
+            Socket::Unix(socket) => { socket.send(frame.as_bytes())?; },
+            Socket::Udp(socket, addr) => { socket.send_to(frame.as_bytes(), *addr)?; },
+            Socket::Tcp(stream, _) => { stream.write_all(frame.as_bytes())?; },
+        }
+
+        Ok(())
+    }
+
+    fn reconnect(&mut self) -> io::Result<()> {
+
+        self.socket = match &self.socket {
+            Socket::Unix(_) => Socket::Unix(connect_unix()?),
+            Socket::Udp(_, addr) => Socket::Udp(bind_udp(*addr)?, *addr),
+            Socket::Tcp(_, addr) => Socket::Tcp(TcpStream::connect(addr)?, *addr),
+        };
+
+        Ok(())
+    }
+}
+
+impl async_logger::Writer<Box<(Level, String)>> for SyslogWriter {
+
+    // Runs on the syslog queue's own writer thread, so framing and the reconnect-and-retry
+    // socket write below never block the thread that called the logging macro.
+    fn process_slice(&mut self, slice: &[Box<(Level, String)>]) {
+        for item in slice {
+            let frame = self.frame(item.0, &item.1);
+
+            let sent = self.send(&frame).is_ok() || match self.reconnect() {
+                Ok(()) => self.send(&frame).is_ok(),
+                Err(_) => false,
+            };
+
+            if !sent {
+                eprintln!("async_logger_log: failed to send record to syslog");
+            }
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+fn connect_unix() -> io::Result<UnixDatagram> {
+    let socket = UnixDatagram::unbound()?;
+    socket.connect("/dev/log")?;
+    Ok(socket)
+}
+
+fn bind_udp(addr: SocketAddr) -> io::Result<UdpSocket> {
+    UdpSocket::bind(if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" })
+}
+
+fn resolve<A: ToSocketAddrs>(addr: A) -> io::Result<SocketAddr> {
+    addr.to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address resolved"))
+}
+
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| std::env::var("HOSTNAME").unwrap_or_else(|_| String::from("unknown")))
+}
+
+#[cfg(feature = "time")]
+fn timestamp() -> String {
+    time::OffsetDateTime::now_utc().format("%Y-%m-%dT%H:%M:%S%z")
+}
+
+#[cfg(not(feature = "time"))]
+fn timestamp() -> String {
+    String::from("-")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_severity_mapping() {
+        assert_eq!(3, severity(Level::Error));
+        assert_eq!(4, severity(Level::Warn));
+        assert_eq!(6, severity(Level::Info));
+        assert_eq!(7, severity(Level::Debug));
+        assert_eq!(7, severity(Level::Trace));
+    }
+}
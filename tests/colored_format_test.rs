@@ -0,0 +1,73 @@
+// test that colored_format wires Logger::colored_msg through a real Logger::log, and that it
+// only emits ANSI escapes when duplicate_to_stderr is also configured
+
+extern crate async_logger;
+
+use async_logger_log::*;
+use async_logger::Writer;
+use log::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SAMPLE_LOG_MSG: &str = "colored-format sample msg";
+
+struct SpyWriter {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl Writer<Box<String>> for SpyWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        let mut lines = self.lines.lock().expect("spy writer lock poisoned");
+        for item in slice {
+            lines.push((**item).clone());
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+fn wait_for<F: Fn() -> bool>(condition: F, timeout: Duration) -> bool {
+
+    let start = Instant::now();
+
+    while !condition() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    true
+}
+
+#[test]
+fn test_colored_format_without_duplicate_to_stderr_has_no_escape_codes() {
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    // Color is only useful for a human watching stderr, so without `duplicate_to_stderr` it
+    // must never be used, regardless of whether the test process's own stderr is a terminal.
+    let logger = Logger::builder()
+        .buf_size(16)
+        .writer(Box::new(SpyWriter { lines: Arc::clone(&lines) }))
+        .colored_format()
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    warn!("{}", SAMPLE_LOG_MSG);
+    log::logger().flush();
+
+    assert!(
+        wait_for(|| lines.lock().expect("lock poisoned").len() == 1, Duration::from_secs(3)),
+        "colored-format record never reached the writer",
+    );
+
+    let lines = lines.lock().expect("lock poisoned");
+    assert!(lines[0].contains(SAMPLE_LOG_MSG));
+    assert!(lines[0].contains("WARN"));
+    assert!(!lines[0].contains("\x1b["), "color shouldn't be used without duplicate_to_stderr");
+}
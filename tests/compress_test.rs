@@ -0,0 +1,91 @@
+// test that CompressingWriter gzip-compresses a segment once rotation closes it
+
+#![cfg(feature = "compress")]
+
+extern crate async_logger;
+extern crate flate2;
+
+use async_logger::{FileWriter, Writer};
+use async_logger_log::compress::CompressingWriter;
+use async_logger_log::*;
+use flate2::read::GzDecoder;
+use log::*;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const LOG_DIR: &str = "/tmp/AsyncLoggerNBCompressTest_93847502934";
+const WRITE_LINE: &str = "compress-test sample log line, padded to force rotation quickly";
+
+fn wait_for<F: Fn() -> bool>(condition: F, timeout: Duration) -> bool {
+
+    let start = Instant::now();
+
+    while !condition() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    true
+}
+
+fn gz_files() -> Vec<std::path::PathBuf> {
+    Path::new(LOG_DIR)
+        .read_dir()
+        .expect("Failed to list files in test directory")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "gz"))
+        .collect()
+}
+
+#[test]
+fn test_compressing_writer_compresses_rotated_segment() {
+
+    if Path::new(LOG_DIR).exists() {
+        std::fs::remove_dir_all(LOG_DIR).expect("Failed to delete test dir on cleanup");
+    }
+
+    std::fs::create_dir(LOG_DIR).expect("Failed to create test dir");
+
+    // A tiny file size means the very first write already exceeds it, so the *next* write
+    // rotates, closing the first segment for `CompressingWriter` to pick up.
+    let file_writer = FileWriter::new(LOG_DIR, 16).expect("Failed to create file writer");
+    let writer: Box<dyn Writer<Box<String>>> = Box::new(CompressingWriter::new(file_writer, LOG_DIR));
+
+    let logger = Logger::builder()
+        .buf_size(16)
+        .writer(writer)
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    info!("{}", WRITE_LINE);
+    log::logger().flush();
+    std::thread::sleep(Duration::from_millis(50));
+
+    info!("{}", WRITE_LINE);
+    log::logger().flush();
+
+    assert!(
+        wait_for(|| !gz_files().is_empty(), Duration::from_secs(3)),
+        "no .gz file appeared after rotation",
+    );
+
+    let gz_files = gz_files();
+    assert_eq!(1, gz_files.len());
+
+    let mut decompressed = String::new();
+    GzDecoder::new(std::fs::File::open(&gz_files[0]).expect("Failed to open compressed segment"))
+        .read_to_string(&mut decompressed)
+        .expect("Failed to decompress segment");
+
+    assert!(decompressed.contains(WRITE_LINE));
+    assert!(!gz_files[0].with_extension("").exists(), "original segment should have been removed after compression");
+
+    std::fs::remove_dir_all(LOG_DIR).expect("Failed to delete test dir on cleanup");
+}
@@ -0,0 +1,74 @@
+// test that LoggerBuilder::filter wires a real per-target directive through Logger::log, not
+// just through Filter::is_enabled in isolation
+
+extern crate async_logger;
+
+use async_logger_log::*;
+use async_logger::Writer;
+use log::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct SpyWriter {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl Writer<Box<String>> for SpyWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        let mut lines = self.lines.lock().expect("spy writer lock poisoned");
+        for item in slice {
+            lines.push((**item).clone());
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+fn wait_for<F: Fn() -> bool>(condition: F, timeout: Duration) -> bool {
+
+    let start = Instant::now();
+
+    while !condition() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    true
+}
+
+#[test]
+fn test_filter_enforces_per_target_directive_through_logger() {
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    let logger = Logger::builder()
+        .buf_size(16)
+        .writer(Box::new(SpyWriter { lines: Arc::clone(&lines) }))
+        .filter("error,filter_test_loud=trace")
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+
+    // Below "error", and not under the loosened target, so this must be dropped before it
+    // ever reaches the writer.
+    log::log!(target: "filter_test_quiet", Level::Info, "quiet target info message");
+
+    // Loosened to "trace" by the per-target directive, so this must get through even though
+    // the default level is "error".
+    log::log!(target: "filter_test_loud", Level::Debug, "loud target debug message");
+
+    log::logger().flush();
+
+    assert!(
+        wait_for(|| !lines.lock().expect("lock poisoned").is_empty(), Duration::from_secs(3)),
+        "directive-matched record never reached the writer",
+    );
+
+    let lines = lines.lock().expect("lock poisoned");
+    assert_eq!(1, lines.len(), "only the directive-matched record should have passed");
+    assert!(lines[0].contains("loud target debug message"));
+}
@@ -0,0 +1,71 @@
+// test that LoggerBuilder::flush_interval flushes the queue in the background without an
+// explicit `log::logger().flush()` call
+
+extern crate async_logger;
+
+use async_logger_log::*;
+use async_logger::Writer;
+use log::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SAMPLE_LOG_MSG: &str = "flush-interval sample msg";
+
+struct SpyWriter {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl Writer<Box<String>> for SpyWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        let mut lines = self.lines.lock().expect("spy writer lock poisoned");
+        for item in slice {
+            lines.push((**item).clone());
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+fn wait_for<F: Fn() -> bool>(condition: F, timeout: Duration) -> bool {
+
+    let start = Instant::now();
+
+    while !condition() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    true
+}
+
+#[test]
+fn test_flush_interval_delivers_without_explicit_flush() {
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    // A buffer this large won't fill from a single record, so the only thing that can deliver
+    // it to the writer is the background flush timer.
+    let logger = Logger::builder()
+        .buf_size(1024)
+        .writer(Box::new(SpyWriter { lines: Arc::clone(&lines) }))
+        .flush_interval(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    info!("{}", SAMPLE_LOG_MSG);
+
+    assert_eq!(0, lines.lock().expect("lock poisoned").len(), "record should still be sitting unflushed in the buffer");
+
+    assert!(
+        wait_for(|| lines.lock().expect("lock poisoned").len() == 1, Duration::from_secs(3)),
+        "background flush timer never delivered the record to the writer",
+    );
+
+    assert!(lines.lock().expect("lock poisoned")[0].contains(SAMPLE_LOG_MSG));
+}
@@ -0,0 +1,78 @@
+// test that LoggerBuilder::json_format wires the Bunyan-style formatter through a real
+// Logger::log, not just through JsonFormatter::format in isolation
+
+#![cfg(feature = "json")]
+
+extern crate async_logger;
+
+use async_logger_log::*;
+use async_logger::Writer;
+use log::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SERVICE_NAME: &str = "json-format-test-service";
+const SAMPLE_LOG_MSG: &str = "json-format sample msg";
+
+struct SpyWriter {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl Writer<Box<String>> for SpyWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        let mut lines = self.lines.lock().expect("spy writer lock poisoned");
+        for item in slice {
+            lines.push((**item).clone());
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+fn wait_for<F: Fn() -> bool>(condition: F, timeout: Duration) -> bool {
+
+    let start = Instant::now();
+
+    while !condition() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    true
+}
+
+#[test]
+fn test_json_format_emits_bunyan_line_through_logger() {
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    let logger = Logger::builder()
+        .buf_size(16)
+        .writer(Box::new(SpyWriter { lines: Arc::clone(&lines) }))
+        .json_format(SERVICE_NAME)
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    warn!("{}", SAMPLE_LOG_MSG);
+    log::logger().flush();
+
+    assert!(
+        wait_for(|| lines.lock().expect("lock poisoned").len() == 1, Duration::from_secs(3)),
+        "json-formatted record never reached the writer",
+    );
+
+    let lines = lines.lock().expect("lock poisoned");
+    let line = lines[0].trim_end();
+
+    assert!(line.starts_with('{'));
+    assert!(line.ends_with('}'));
+    assert!(line.contains(&format!("\"name\":\"{}\"", SERVICE_NAME)));
+    assert!(line.contains("\"level\":40")); // Bunyan level for Warn
+    assert!(line.contains(&format!("\"msg\":\"{}\"", SAMPLE_LOG_MSG)));
+}
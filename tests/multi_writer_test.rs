@@ -0,0 +1,72 @@
+// test add_writer fanning records out to several destinations alongside duplicate_to_stderr
+
+extern crate async_logger;
+
+use async_logger_log::*;
+use async_logger::Writer;
+use log::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SAMPLE_LOG_MSG: &str = "multi-writer sample msg";
+
+struct SpyWriter {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+// `AsyncLoggerNB::flush` only marks the buffer readable; it doesn't wait for the writer thread
+// to drain it, so tests have to poll for the expected record count to show up.
+fn wait_for<F: Fn() -> bool>(condition: F) {
+
+    let start = Instant::now();
+
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out waiting for writer thread");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+impl Writer<Box<String>> for SpyWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        let mut lines = self.lines.lock().expect("spy writer lock poisoned");
+        for item in slice {
+            lines.push((**item).clone());
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+#[test]
+fn test_add_writer_reaches_every_destination_unmodified() {
+
+    let primary = Arc::new(Mutex::new(Vec::new()));
+    let secondary = Arc::new(Mutex::new(Vec::new()));
+
+    let logger = Logger::builder()
+        .buf_size(16)
+        .writer(Box::new(SpyWriter { lines: Arc::clone(&primary) }))
+        .add_writer(Box::new(SpyWriter { lines: Arc::clone(&secondary) }))
+        .duplicate_to_stderr(Level::Warn)
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    warn!("{}", SAMPLE_LOG_MSG);
+
+    log::logger().flush();
+
+    wait_for(|| primary.lock().expect("primary lock poisoned").len() == 1
+        && secondary.lock().expect("secondary lock poisoned").len() == 1);
+
+    let primary = primary.lock().expect("primary lock poisoned");
+    let secondary = secondary.lock().expect("secondary lock poisoned");
+
+    assert_eq!(1, primary.len());
+    assert_eq!(1, secondary.len());
+    assert!(primary[0].contains(SAMPLE_LOG_MSG));
+    assert_eq!(primary[0], secondary[0]);
+}
@@ -0,0 +1,85 @@
+// test that LoggerBuilder::spec_file hot-reloads the filter from a real file on disk
+
+extern crate async_logger;
+
+use async_logger_log::*;
+use async_logger::Writer;
+use log::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const LOOSE_MSG: &str = "spec-file-test loosened message";
+
+struct SpyWriter {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl Writer<Box<String>> for SpyWriter {
+
+    fn process_slice(&mut self, slice: &[Box<String>]) {
+        let mut lines = self.lines.lock().expect("spy writer lock poisoned");
+        for item in slice {
+            lines.push((**item).clone());
+        }
+    }
+
+    fn flush(&mut self) { }
+}
+
+fn wait_for<F: Fn() -> bool>(condition: F, timeout: Duration) -> bool {
+
+    let start = Instant::now();
+
+    while !condition() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    true
+}
+
+#[test]
+fn test_spec_file_hot_reload() {
+
+    let spec_path = std::env::temp_dir().join("async_logger_log_spec_file_test.spec");
+    std::fs::write(&spec_path, "error").expect("Failed to write initial spec file");
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+
+    let logger = Logger::builder()
+        .buf_size(16)
+        .writer(Box::new(SpyWriter { lines: Arc::clone(&lines) }))
+        .spec_file(&spec_path)
+        .build()
+        .unwrap();
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to set logger");
+
+    debug!("{}", LOOSE_MSG);
+    log::logger().flush();
+    std::thread::sleep(Duration::from_millis(100));
+
+    assert_eq!(0, lines.lock().expect("lock poisoned").len(), "debug! should be suppressed by the initial 'error' spec");
+
+    std::fs::write(&spec_path, "trace").expect("Failed to rewrite spec file");
+
+    // the watcher polls once a second, so give it a couple of cycles to notice the change
+    assert!(
+        wait_for(|| log::max_level() == log::LevelFilter::Trace, Duration::from_secs(3)),
+        "spec file watcher did not raise log::max_level() after reload",
+    );
+
+    debug!("{}", LOOSE_MSG);
+    log::logger().flush();
+
+    assert!(
+        wait_for(|| lines.lock().expect("lock poisoned").len() == 1, Duration::from_secs(3)),
+        "debug! was not let through after the spec file was loosened to 'trace'",
+    );
+
+    assert!(lines.lock().expect("lock poisoned")[0].contains(LOOSE_MSG));
+
+    std::fs::remove_file(&spec_path).expect("Failed to delete spec file on cleanup");
+}